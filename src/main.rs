@@ -1,27 +1,46 @@
 use itertools::Itertools;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use std::{env, io};
 use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::io::{BufRead, BufReader, Write};
 use std::ops::{AddAssign};
 use std::str::FromStr;
 use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
 static DATE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*→\s*(.*?)\s*$").unwrap());
 static ALBUM_ENTRY_PATTERN: LazyLock<Regex> =
 	LazyLock::new(|| Regex::new(r"^\s*(.+?)\s*(?:\((\d+)x\))?$").unwrap());
+static FULL_DATE_PATTERN: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"^(\d{4})-(\d{2})-\d{2}$").unwrap());
+static MONTH_DATE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d{4})-(\d{2})$").unwrap());
 const TOP_ALBUMS: usize = 20usize;
 const TOP_ARTISTS: usize = 10usize;
 const ENTRY_SEPARATOR: char = '–';
 const ARTIST_JOINER: char = '/';
+const LEADING_ARTICLES: [&str; 3] = ["the ", "a ", "an "];
+const MUSICBRAINZ_RATE_LIMIT: Duration = Duration::from_secs(1);
+const MUSICBRAINZ_USER_AGENT: &str = "album-log/0.1 (+https://github.com/Anxiko/album-log)";
+const DEFAULT_MUSICBRAINZ_CACHE: &str = "musicbrainz-cache.json";
+
+/// A `(year, month)` pair parsed out of a `→ date` header, when the header is recognisable.
+type ParsedDate = Option<(i32, u32)>;
+
+fn parse_date_header(raw: &str) -> ParsedDate {
+	FULL_DATE_PATTERN
+		.captures(raw)
+		.or_else(|| MONTH_DATE_PATTERN.captures(raw))
+		.and_then(|caps| Some((caps[1].parse().ok()?, caps[2].parse().ok()?)))
+}
 
 enum ParsedLine {
 	Entry(FreqEntry<String>),
-	Date(String),
+	Date(String, ParsedDate),
 }
 
 impl FromStr for ParsedLine {
@@ -30,7 +49,11 @@ impl FromStr for ParsedLine {
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		DATE_PATTERN
 			.captures(s)
-			.map(|date_match| ParsedLine::Date(date_match[1].to_owned()))
+			.map(|date_match| {
+				let date = date_match[1].to_owned();
+				let parsed_date = parse_date_header(&date);
+				ParsedLine::Date(date, parsed_date)
+			})
 			.or_else(|| {
 				ALBUM_ENTRY_PATTERN.captures(s).map(|album_match| {
 					ParsedLine::Entry(FreqEntry::new(
@@ -46,8 +69,16 @@ impl FromStr for ParsedLine {
 	}
 }
 
+/// Entries logged under a single `→ date` header, alongside the calendar date parsed from it
+/// (when the header is in a recognised format).
+#[derive(Default)]
+struct DateBucket {
+	parsed_date: ParsedDate,
+	entries: Vec<FreqEntry<String>>,
+}
+
 struct AlbumLog {
-	entries: HashMap<String, Vec<FreqEntry<String>>>,
+	entries: HashMap<String, DateBucket>,
 	current: Option<String>,
 }
 
@@ -61,7 +92,8 @@ impl AlbumLog {
 
 	fn feed_line(&mut self, line: ParsedLine) {
 		match line {
-			ParsedLine::Date(date) => {
+			ParsedLine::Date(date, parsed_date) => {
+				self.entries.entry(date.clone()).or_default().parsed_date = parsed_date;
 				self.current = Some(date);
 			}
 			ParsedLine::Entry(entry) => {
@@ -69,6 +101,7 @@ impl AlbumLog {
 					self.entries
 						.entry(current_date.clone())
 						.or_default()
+						.entries
 						.push(entry);
 				} else {
 					// println!("Skipping entry before first date: {entry}")
@@ -77,8 +110,11 @@ impl AlbumLog {
 		}
 	}
 
-	fn flattened_album_entries(&self) -> impl Iterator<Item=&FreqEntry<String>> {
-		self.entries.values().flatten()
+	/// Every logged entry alongside the calendar date of the `→` header it was logged under.
+	fn dated_album_entries(&self) -> impl Iterator<Item=(ParsedDate, &FreqEntry<String>)> {
+		self.entries
+			.values()
+			.flat_map(|bucket| bucket.entries.iter().map(move |entry| (bucket.parsed_date, entry)))
 	}
 }
 
@@ -185,13 +221,791 @@ impl<T: Eq + Ord + Hash> Counter<T> {
 	}
 }
 
+/// A display name paired with a separately-derived sort key, e.g. `"beatles"` for `"The Beatles"`.
+/// `Eq`/`Ord`/`Hash` all compare the sort key (case-folded, leading article stripped) so variant
+/// spellings and "The X" vs "X" collate and merge together, while `Display` always shows the
+/// canonical surface form.
+#[derive(Clone)]
+struct SortedName {
+	display: String,
+	sort_key: String,
+}
+
+impl SortedName {
+	fn new(display: String) -> Self {
+		let sort_key = Self::sort_key_for(&display);
+		Self { display, sort_key }
+	}
+
+	/// Builds a name whose `Eq`/`Ord`/`Hash` are driven by an explicit `sort_key` instead of one
+	/// derived from `display`, e.g. a MusicBrainz MBID so differently-spelled entries resolving to
+	/// the same release group merge together.
+	fn with_key(display: String, sort_key: String) -> Self {
+		Self { display, sort_key }
+	}
+
+	fn sort_key_for(display: &str) -> String {
+		let folded = display.to_lowercase();
+		LEADING_ARTICLES
+			.iter()
+			.find_map(|article| folded.strip_prefix(article).map(str::to_owned))
+			.unwrap_or(folded)
+	}
+}
+
+impl PartialEq for SortedName {
+	fn eq(&self, other: &Self) -> bool {
+		self.sort_key == other.sort_key
+	}
+}
+
+impl Eq for SortedName {}
+
+impl PartialOrd for SortedName {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for SortedName {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.sort_key.cmp(&other.sort_key)
+	}
+}
+
+impl Hash for SortedName {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.sort_key.hash(state);
+	}
+}
+
+impl Display for SortedName {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.display)
+	}
+}
+
+/// Resolves raw artist/album names to a canonical [`SortedName`], collapsing known spelling
+/// variants and `feat.`/`ft.` guest credits onto a primary name via an optional alias file
+/// (`raw name = canonical name`, one mapping per line, matched case-insensitively).
+#[derive(Default)]
+struct Canonicalizer {
+	aliases: HashMap<String, String>,
+}
+
+impl Canonicalizer {
+	fn new() -> Self {
+		Self::default()
+	}
+
+	fn load(path: &str) -> anyhow::Result<Self> {
+		let file = File::open(path)?;
+		let aliases = BufReader::new(file)
+			.lines()
+			.map(|line| line.expect("Read alias file line"))
+			.filter(|line| !line.trim().is_empty())
+			.map(|line| {
+				let (raw, canonical) = line
+					.split_once('=')
+					.ok_or_else(|| anyhow::anyhow!("Alias line must be `name = canonical name`: {line:?}"))?;
+				Ok((raw.trim().to_lowercase(), canonical.trim().to_owned()))
+			})
+			.collect::<anyhow::Result<HashMap<_, _>>>()?;
+		Ok(Self { aliases })
+	}
+
+	fn canonicalize(&self, raw: &str) -> SortedName {
+		let display = self.aliases.get(&raw.to_lowercase()).cloned().unwrap_or_else(|| raw.to_owned());
+		SortedName::new(display)
+	}
+
+	/// Canonicalizes a raw `"Artist – Album"` log entry on the album-title axis: the artist prefix
+	/// and the album title are each matched against the alias file and article-stripped, and the
+	/// sort key combines both, so an alias like `"OK Computer (Remaster) = OK Computer"` collapses
+	/// the title while entries from different artists don't fold into each other. The display
+	/// keeps the artist prefix as logged and shows the canonical album title.
+	fn canonicalize_album_entry(&self, raw: &str) -> SortedName {
+		let Some((artists, album_title)) = raw.split_once(ENTRY_SEPARATOR) else {
+			return self.canonicalize(raw);
+		};
+		let artists = artists.trim();
+		let canonical_artists = self.canonicalize(artists);
+		let canonical_title = self.canonicalize(album_title.trim());
+
+		SortedName::with_key(
+			format!("{artists} {ENTRY_SEPARATOR} {}", canonical_title.display),
+			format!("{}\u{0}{}", canonical_artists.sort_key, canonical_title.sort_key),
+		)
+	}
+}
+
+/// A release group resolved from MusicBrainz for a raw `(artists, album)` pair: its MBID, the
+/// canonical artist credit and title, and (when known) its first release year.
+#[derive(Clone, Serialize, Deserialize)]
+struct MusicBrainzResolution {
+	mbid: String,
+	artist_credit: String,
+	release_group_title: String,
+	release_year: Option<i32>,
+}
+
+impl MusicBrainzResolution {
+	/// The canonical `"Artist – Album"` text, in the same shape as a logged entry, so it can be
+	/// fed back through [`Canonicalizer::canonicalize`] and parsed by [`get_artists`].
+	fn canonical_name(&self) -> SortedName {
+		SortedName::with_key(
+			format!("{} {ENTRY_SEPARATOR} {}", self.artist_credit, self.release_group_title),
+			self.mbid.clone(),
+		)
+	}
+}
+
+#[derive(Deserialize)]
+struct MusicBrainzSearchResponse {
+	#[serde(rename = "release-groups")]
+	release_groups: Vec<MusicBrainzReleaseGroup>,
+}
+
+#[derive(Deserialize)]
+struct MusicBrainzReleaseGroup {
+	id: String,
+	title: String,
+	#[serde(rename = "first-release-date")]
+	first_release_date: Option<String>,
+	#[serde(rename = "artist-credit")]
+	artist_credit: Vec<MusicBrainzArtistCredit>,
+}
+
+#[derive(Deserialize)]
+struct MusicBrainzArtistCredit {
+	name: String,
+}
+
+/// Resolves raw logged `(artists, album)` text to a canonical MusicBrainz release group, caching
+/// results to a local JSON file keyed by the raw text so repeat runs don't re-query, and sleeping
+/// between lookups to respect MusicBrainz's 1 request/second rate limit.
+struct MusicBrainzClient {
+	cache_path: String,
+	cache: HashMap<String, Option<MusicBrainzResolution>>,
+	last_request: Option<Instant>,
+}
+
+impl MusicBrainzClient {
+	fn load(cache_path: &str) -> anyhow::Result<Self> {
+		let cache = match std::fs::read_to_string(cache_path) {
+			Ok(contents) => serde_json::from_str(&contents)?,
+			Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+			Err(err) => return Err(err.into()),
+		};
+		Ok(Self { cache_path: cache_path.to_owned(), cache, last_request: None })
+	}
+
+	fn save(&self) -> anyhow::Result<()> {
+		std::fs::write(&self.cache_path, serde_json::to_string_pretty(&self.cache)?)?;
+		Ok(())
+	}
+
+	/// Resolves `raw` (the full logged entry text) via the cache, falling back to a live
+	/// MusicBrainz search when it isn't known yet. Returns `None` when `raw` isn't a parseable
+	/// `artists – album` entry, or when MusicBrainz has no matching release group; a lookup with no
+	/// match is cached too, so an unresolvable entry isn't re-queried on every run.
+	fn resolve(&mut self, raw: &str) -> anyhow::Result<Option<MusicBrainzResolution>> {
+		if let Some(cached) = self.cache.get(raw) {
+			return Ok(cached.clone());
+		}
+
+		let Ok(artists) = get_artists(raw) else { return Ok(None); };
+		let Some(album) = get_album_title(raw) else { return Ok(None); };
+
+		self.throttle();
+		let resolution = Self::search(&artists, &album)?;
+		self.cache.insert(raw.to_owned(), resolution.clone());
+		self.save()?;
+		Ok(resolution)
+	}
+
+	fn throttle(&mut self) {
+		if let Some(last_request) = self.last_request {
+			let elapsed = last_request.elapsed();
+			if elapsed < MUSICBRAINZ_RATE_LIMIT {
+				std::thread::sleep(MUSICBRAINZ_RATE_LIMIT - elapsed);
+			}
+		}
+		self.last_request = Some(Instant::now());
+	}
+
+	fn search(artists: &[String], album: &str) -> anyhow::Result<Option<MusicBrainzResolution>> {
+		let query = format!("releasegroup:\"{album}\" AND artist:\"{}\"", artists.join(" "));
+		let url = format!(
+			"https://musicbrainz.org/ws/2/release-group/?query={}&fmt=json&limit=1",
+			percent_encode(&query)
+		);
+
+		let response: MusicBrainzSearchResponse =
+			ureq::get(&url).set("User-Agent", MUSICBRAINZ_USER_AGENT).call()?.into_json()?;
+
+		Ok(response.release_groups.into_iter().next().map(|group| MusicBrainzResolution {
+			mbid: group.id,
+			artist_credit: group.artist_credit.into_iter().map(|credit| credit.name).join(&ARTIST_JOINER.to_string()),
+			release_group_title: group.title,
+			release_year: group.first_release_date.and_then(|date| date.get(0..4)?.parse().ok()),
+		}))
+	}
+}
+
+fn percent_encode(raw: &str) -> String {
+	let mut encoded = String::new();
+	for byte in raw.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+			_ => encoded.push_str(&format!("%{byte:02X}")),
+		}
+	}
+	encoded
+}
+
+/// One token of a `--query` expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	LParen,
+	RParen,
+	And,
+	Or,
+	Not,
+	Field(String),
+	Op(String),
+	Str(String),
+	Num(u32),
+	Raw(String),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+	let mut tokens = Vec::new();
+	let mut chars = query.chars().peekable();
+
+	while let Some(&c) = chars.peek() {
+		match c {
+			c if c.is_whitespace() => {
+				chars.next();
+			}
+			'(' => {
+				chars.next();
+				tokens.push(Token::LParen);
+			}
+			')' => {
+				chars.next();
+				tokens.push(Token::RParen);
+			}
+			'~' => {
+				chars.next();
+				tokens.push(Token::Op("~".to_owned()));
+			}
+			'>' | '<' | '=' => {
+				chars.next();
+				let mut op = c.to_string();
+				if chars.peek() == Some(&'=') {
+					chars.next();
+					op.push('=');
+				}
+				tokens.push(Token::Op(op));
+			}
+			'"' => {
+				chars.next();
+				let mut value = String::new();
+				loop {
+					match chars.next() {
+						Some('"') => break,
+						Some(ch) => value.push(ch),
+						None => return Err(format!("Unterminated string literal in query: {query:?}")),
+					}
+				}
+				tokens.push(Token::Str(value));
+			}
+			_ => {
+				let mut word = String::new();
+				while let Some(&ch) = chars.peek() {
+					if ch.is_whitespace() || "()~><=\"".contains(ch) {
+						break;
+					}
+					word.push(ch);
+					chars.next();
+				}
+				tokens.push(match word.as_str() {
+					"and" => Token::And,
+					"or" => Token::Or,
+					"not" => Token::Not,
+					"artist" | "album" | "freq" | "date" => Token::Field(word),
+					_ => word.parse().map(Token::Num).unwrap_or(Token::Raw(word)),
+				});
+			}
+		}
+	}
+
+	Ok(tokens)
+}
+
+/// A comparison operator usable on the `freq`/`date` fields.
+#[derive(Clone, Copy)]
+enum CompareOp {
+	Lt,
+	Le,
+	Gt,
+	Ge,
+	Eq,
+}
+
+impl CompareOp {
+	fn from_op(op: &str) -> Option<Self> {
+		match op {
+			"<" => Some(CompareOp::Lt),
+			"<=" => Some(CompareOp::Le),
+			">" => Some(CompareOp::Gt),
+			">=" => Some(CompareOp::Ge),
+			"==" => Some(CompareOp::Eq),
+			_ => None,
+		}
+	}
+
+	fn apply<T: PartialOrd>(&self, lhs: T, rhs: T) -> bool {
+		match self {
+			CompareOp::Lt => lhs < rhs,
+			CompareOp::Le => lhs <= rhs,
+			CompareOp::Gt => lhs > rhs,
+			CompareOp::Ge => lhs >= rhs,
+			CompareOp::Eq => lhs == rhs,
+		}
+	}
+}
+
+/// The right-hand side of a `date` comparison: either a bare year or a `YYYY-MM` month.
+enum DateBound {
+	Year(i32),
+	Month(i32, u32),
+}
+
+fn parse_date_bound(raw: &str) -> Option<DateBound> {
+	if let Some((year, month)) = parse_date_header(raw) {
+		return Some(DateBound::Month(year, month));
+	}
+	raw.parse().ok().map(DateBound::Year)
+}
+
+impl DateBound {
+	fn compare(&self, actual: (i32, u32), op: CompareOp) -> bool {
+		match self {
+			DateBound::Year(year) => op.apply(actual.0, *year),
+			DateBound::Month(year, month) => {
+				op.apply(actual.0 * 12 + actual.1 as i32, year * 12 + *month as i32)
+			}
+		}
+	}
+}
+
+/// A filter over logged entries, as parsed from a `--query` expression. Combines `artist`/`album`
+/// regex matches with `freq`/`date` comparisons via `and`/`or`/`not`.
+enum Predicate {
+	ArtistMatches(Regex),
+	AlbumMatches(Regex),
+	FreqCompare(CompareOp, u32),
+	DateCompare(CompareOp, DateBound),
+	And(Box<Predicate>, Box<Predicate>),
+	Or(Box<Predicate>, Box<Predicate>),
+	Not(Box<Predicate>),
+}
+
+impl Predicate {
+	fn matches(&self, entry: &FreqEntry<String>, date: ParsedDate) -> bool {
+		match self {
+			Predicate::ArtistMatches(regex) => get_artists(&entry.value)
+				.map(|artists| artists.iter().any(|artist| regex.is_match(artist)))
+				.unwrap_or(false),
+			Predicate::AlbumMatches(regex) => get_album_title(&entry.value)
+				.map(|album| regex.is_match(&album))
+				.unwrap_or(false),
+			Predicate::FreqCompare(op, value) => op.apply(entry.freq, *value),
+			Predicate::DateCompare(op, bound) => date.map(|actual| bound.compare(actual, *op)).unwrap_or(false),
+			Predicate::And(lhs, rhs) => lhs.matches(entry, date) && rhs.matches(entry, date),
+			Predicate::Or(lhs, rhs) => lhs.matches(entry, date) || rhs.matches(entry, date),
+			Predicate::Not(inner) => !inner.matches(entry, date),
+		}
+	}
+}
+
+impl FromStr for Predicate {
+	type Err = String;
+
+	fn from_str(query: &str) -> Result<Self, Self::Err> {
+		let tokens = tokenize(query)?;
+		let mut parser = PredicateParser { tokens: &tokens, pos: 0 };
+		let predicate = parser.parse_or()?;
+		if parser.pos != tokens.len() {
+			return Err(format!("Unexpected trailing input in query: {query:?}"));
+		}
+		Ok(predicate)
+	}
+}
+
+/// Recursive-descent parser over `Token`s, lowest to highest precedence: `or`, `and`, `not`,
+/// then a parenthesised sub-expression or a single `field op value` comparison.
+struct PredicateParser<'a> {
+	tokens: &'a [Token],
+	pos: usize,
+}
+
+impl<'a> PredicateParser<'a> {
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.pos)
+	}
+
+	fn advance(&mut self) -> Option<&Token> {
+		let token = self.tokens.get(self.pos);
+		self.pos += 1;
+		token
+	}
+
+	fn parse_or(&mut self) -> Result<Predicate, String> {
+		let mut lhs = self.parse_and()?;
+		while matches!(self.peek(), Some(Token::Or)) {
+			self.advance();
+			let rhs = self.parse_and()?;
+			lhs = Predicate::Or(Box::new(lhs), Box::new(rhs));
+		}
+		Ok(lhs)
+	}
+
+	fn parse_and(&mut self) -> Result<Predicate, String> {
+		let mut lhs = self.parse_unary()?;
+		while matches!(self.peek(), Some(Token::And)) {
+			self.advance();
+			let rhs = self.parse_unary()?;
+			lhs = Predicate::And(Box::new(lhs), Box::new(rhs));
+		}
+		Ok(lhs)
+	}
+
+	fn parse_unary(&mut self) -> Result<Predicate, String> {
+		if matches!(self.peek(), Some(Token::Not)) {
+			self.advance();
+			return Ok(Predicate::Not(Box::new(self.parse_unary()?)));
+		}
+		self.parse_primary()
+	}
+
+	fn parse_primary(&mut self) -> Result<Predicate, String> {
+		match self.advance().cloned() {
+			Some(Token::LParen) => {
+				let inner = self.parse_or()?;
+				match self.advance() {
+					Some(Token::RParen) => Ok(inner),
+					other => Err(format!("Expected closing ')', found {other:?}")),
+				}
+			}
+			Some(Token::Field(field)) => self.parse_comparison(field),
+			other => Err(format!("Expected a predicate, found {other:?}")),
+		}
+	}
+
+	fn parse_comparison(&mut self, field: String) -> Result<Predicate, String> {
+		let op = match self.advance() {
+			Some(Token::Op(op)) => op.clone(),
+			other => return Err(format!("Expected a comparison operator after `{field}`, found {other:?}")),
+		};
+
+		match field.as_str() {
+			"artist" | "album" => {
+				if op != "~" {
+					return Err(format!("`{field}` only supports the `~` operator"));
+				}
+				let pattern = match self.advance() {
+					Some(Token::Str(pattern)) => pattern.clone(),
+					other => return Err(format!("Expected a quoted string after `{field} ~`, found {other:?}")),
+				};
+				let regex = Regex::new(&format!("(?i){pattern}")).map_err(|err| err.to_string())?;
+				Ok(if field == "artist" { Predicate::ArtistMatches(regex) } else { Predicate::AlbumMatches(regex) })
+			}
+			"freq" => {
+				let compare_op =
+					CompareOp::from_op(&op).ok_or_else(|| format!("Unsupported operator `{op}` for `freq`"))?;
+				let value = match self.advance() {
+					Some(Token::Num(value)) => *value,
+					other => return Err(format!("Expected a number after `freq {op}`, found {other:?}")),
+				};
+				Ok(Predicate::FreqCompare(compare_op, value))
+			}
+			"date" => {
+				let compare_op =
+					CompareOp::from_op(&op).ok_or_else(|| format!("Unsupported operator `{op}` for `date`"))?;
+				let raw = match self.advance() {
+					Some(Token::Raw(raw)) => raw.clone(),
+					Some(Token::Num(value)) => value.to_string(),
+					other => return Err(format!("Expected a date after `date {op}`, found {other:?}")),
+				};
+				let bound = parse_date_bound(&raw).ok_or_else(|| format!("Invalid date literal: {raw:?}"))?;
+				Ok(Predicate::DateCompare(compare_op, bound))
+			}
+			_ => unreachable!("tokenizer only ever emits known field names"),
+		}
+	}
+}
+
+/// The time window that `--by` groups top-albums/top-artists tables into.
+#[derive(Clone, Copy)]
+enum Granularity {
+	Month,
+	Year,
+}
+
+impl FromStr for Granularity {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"month" => Ok(Granularity::Month),
+			"year" => Ok(Granularity::Year),
+			_ => Err(()),
+		}
+	}
+}
+
+/// The period a dated entry was grouped into, at whatever granularity was requested.
+/// Entries whose date header couldn't be parsed land in `Unknown` rather than being dropped.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Period {
+	Year(i32),
+	Month(i32, u32),
+	Unknown,
+}
+
+impl Period {
+	fn of(date: ParsedDate, granularity: Granularity) -> Self {
+		match (date, granularity) {
+			(Some((year, _)), Granularity::Year) => Period::Year(year),
+			(Some((year, month)), Granularity::Month) => Period::Month(year, month),
+			(None, _) => Period::Unknown,
+		}
+	}
+}
+
+impl Display for Period {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Period::Year(year) => write!(f, "{year}"),
+			Period::Month(year, month) => write!(f, "{year}-{month:02}"),
+			Period::Unknown => write!(f, "unknown"),
+		}
+	}
+}
+
+/// Every dated entry that satisfies `predicate` (all of them, if there isn't one).
+fn filter_dated_entries<'a>(
+	log: &'a AlbumLog,
+	predicate: Option<&'a Predicate>,
+) -> impl Iterator<Item=(ParsedDate, &'a FreqEntry<String>)> {
+	log.dated_album_entries()
+		.filter(move |(date, entry)| predicate.map(|predicate| predicate.matches(entry, *date)).unwrap_or(true))
+}
+
+/// Sum of listens per canonical album name, grouped into periods at the given granularity.
+fn group_album_freq<'a>(
+	entries: impl Iterator<Item=(ParsedDate, &'a FreqEntry<String>)>,
+	granularity: Granularity,
+	resolved_names: &HashMap<String, SortedName>,
+) -> HashMap<Period, HashMap<SortedName, u32>> {
+	entries.fold(HashMap::new(), |mut acc, (date, entry)| {
+		acc.entry(Period::of(date, granularity))
+			.or_insert_with(HashMap::new)
+			.entry(resolved_names[&entry.value].clone())
+			.or_default()
+			.add_assign(entry.freq);
+		acc
+	})
+}
+
+/// Runs the usual top-albums/top-artists pipeline once per period, oldest first.
+fn print_periodic_tables(
+	log: &AlbumLog,
+	granularity: Granularity,
+	resolved_names: &HashMap<String, SortedName>,
+	canonicalizer: &Canonicalizer,
+	predicate: Option<&Predicate>,
+) {
+	let groups = group_album_freq(filter_dated_entries(log, predicate), granularity, resolved_names);
+
+	for period in groups.keys().sorted() {
+		println!("\n== {period} ==");
+		let ranked_albums = RankedEntry::from_freq_entries(
+			groups[period].iter().map(|(album, freq)| FreqEntry::new(*freq, album.clone())),
+		);
+		print_top(
+			&ranked_albums,
+			TOP_ALBUMS,
+			|unique, total| format!("{unique} albums listed, {total} albums listened"),
+		);
+
+		let artist_counter = ranked_albums
+			.iter()
+			.flat_map(|ranked_entry| {
+				get_artists(&ranked_entry.freq_entry.value.display)
+					.into_iter()
+					.flatten()
+					.map(|artist| (canonicalizer.canonicalize(&artist), ranked_entry.freq_entry.freq))
+			})
+			.fold(Counter::new(), |mut acc, (artist, freq)| {
+				acc.add(artist, freq);
+				acc
+			});
+		let ranked_artists = RankedEntry::from_freq_entries(artist_counter.to_freq_entries());
+		print_top(
+			&ranked_artists,
+			TOP_ARTISTS,
+			|unique, total| format!("{unique} artists listed, {total} artists listened"),
+		);
+	}
+}
+
+/// Total listens per month, in chronological order, as a simple trend line.
+fn print_monthly_trend(log: &AlbumLog, predicate: Option<&Predicate>) {
+	let totals =
+		filter_dated_entries(log, predicate).fold(HashMap::<Period, u32>::new(), |mut acc, (date, entry)| {
+			acc.entry(Period::of(date, Granularity::Month)).or_default().add_assign(entry.freq);
+			acc
+		});
+
+	println!("\n== Monthly trend ==");
+	for period in totals.keys().sorted() {
+		println!("{period}: {}", totals[period]);
+	}
+}
+
+/// For each of the overall top albums, the month in which it racked up the most listens.
+fn print_album_peaks(
+	log: &AlbumLog,
+	ranked_albums: &[RankedEntry<SortedName>],
+	resolved_names: &HashMap<String, SortedName>,
+	predicate: Option<&Predicate>,
+) {
+	let monthly = group_album_freq(filter_dated_entries(log, predicate), Granularity::Month, resolved_names);
+
+	println!("\n== Peak month per top album ==");
+	for ranked in ranked_albums.iter().take(TOP_ALBUMS) {
+		let album = &ranked.freq_entry.value;
+		let peak = monthly
+			.iter()
+			.filter(|(period, _)| **period != Period::Unknown)
+			.filter_map(|(period, freqs)| freqs.get(album).map(|freq| (*period, *freq)))
+			.max_by_key(|(_, freq)| *freq);
+
+		match peak {
+			Some((period, freq)) => println!("{album}: peaked in {period} ({freq}x)"),
+			None => println!("{album}: no dated listens"),
+		}
+	}
+}
+
+/// Longest run of consecutive calendar months in which `artist` appears at least once. Artist
+/// identity is derived from each entry's resolved album name (so MusicBrainz-unified artist
+/// credits match), not the raw logged text.
+fn longest_monthly_streak(
+	log: &AlbumLog,
+	artist: &SortedName,
+	resolved_names: &HashMap<String, SortedName>,
+	canonicalizer: &Canonicalizer,
+	predicate: Option<&Predicate>,
+) -> u32 {
+	let months = filter_dated_entries(log, predicate)
+		.filter(|(_, entry)| {
+			get_artists(&resolved_names[&entry.value].display)
+				.map(|artists| artists.iter().any(|candidate| canonicalizer.canonicalize(candidate) == *artist))
+				.unwrap_or(false)
+		})
+		.filter_map(|(date, _)| date.map(|(year, month)| year * 12 + month as i32))
+		.unique()
+		.sorted()
+		.collect_vec();
+
+	let mut longest = 0u32;
+	let mut current = 0u32;
+	let mut previous: Option<i32> = None;
+	for month in months {
+		current = if previous == Some(month - 1) { current + 1 } else { 1 };
+		longest = longest.max(current);
+		previous = Some(month);
+	}
+	longest
+}
+
+/// For each of the overall top artists, their longest streak of back-to-back active months.
+fn print_artist_streaks(
+	log: &AlbumLog,
+	ranked_artists: &[RankedEntry<SortedName>],
+	resolved_names: &HashMap<String, SortedName>,
+	canonicalizer: &Canonicalizer,
+	predicate: Option<&Predicate>,
+) {
+	println!("\n== Longest monthly streak per top artist ==");
+	for ranked in ranked_artists.iter().take(TOP_ARTISTS) {
+		let artist = &ranked.freq_entry.value;
+		println!(
+			"{artist}: {} consecutive month(s)",
+			longest_monthly_streak(log, artist, resolved_names, canonicalizer, predicate)
+		);
+	}
+}
+
+struct Args {
+	file: String,
+	by: Option<Granularity>,
+	alias_file: Option<String>,
+	query: Option<String>,
+	query_file: Option<String>,
+	format: Format,
+	interactive: bool,
+	musicbrainz: bool,
+	musicbrainz_cache: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Option<Args> {
+	let mut iter = args.iter();
+	let file = iter.next()?.clone();
+	let mut by = None;
+	let mut alias_file = None;
+	let mut query = None;
+	let mut query_file = None;
+	let mut format = Format::Text;
+	let mut interactive = false;
+	let mut musicbrainz = false;
+	let mut musicbrainz_cache = None;
+
+	while let Some(flag) = iter.next() {
+		match flag.as_str() {
+			"--by" => by = Some(iter.next()?.parse().ok()?),
+			"--alias-file" => alias_file = Some(iter.next()?.clone()),
+			"--query" => query = Some(iter.next()?.clone()),
+			"--query-file" => query_file = Some(iter.next()?.clone()),
+			"--format" => format = iter.next()?.parse().ok()?,
+			"--interactive" => interactive = true,
+			"--musicbrainz" => musicbrainz = true,
+			"--musicbrainz-cache" => musicbrainz_cache = Some(iter.next()?.clone()),
+			_ => return None,
+		}
+	}
+
+	Some(Args { file, by, alias_file, query, query_file, format, interactive, musicbrainz, musicbrainz_cache })
+}
+
 fn main() -> anyhow::Result<()> {
 	match &env::args().collect_vec()[..] {
-		[_name, file] => process_file(file),
-		[name, ..] => {
-			eprintln!("Usage: {name} <file.txt>");
-			Ok(())
-		}
+		[_name, rest @ ..] => match parse_args(rest) {
+			Some(args) => process_file(args),
+			None => {
+				eprintln!(
+					"Usage: {_name} <file.txt> [--by month|year] [--alias-file <path>] \
+					 [--query <expr> | --query-file <path>] [--format text|json|csv] [--interactive] \
+					 [--musicbrainz] [--musicbrainz-cache <path>]"
+				);
+				Ok(())
+			}
+		},
 		_ => unreachable!(),
 	}
 }
@@ -201,6 +1015,11 @@ fn get_artists(album_entry: &str) -> Result<Vec<String>, ()> {
 	Ok(artists.split(ARTIST_JOINER).map(|s| s.trim().to_owned()).collect_vec())
 }
 
+fn get_album_title(album_entry: &str) -> Option<String> {
+	let (_, album) = album_entry.split_once(ENTRY_SEPARATOR)?;
+	Some(album.trim().to_owned())
+}
+
 fn prompt() -> Option<bool> {
 	print!("See all? [Y/n]: ");
 	io::stdout().flush().expect("Flush STDOUT");
@@ -217,20 +1036,27 @@ fn prompt() -> Option<bool> {
 	}
 }
 
-fn print_top<T: Eq + Ord + Display>(ranked_entries: &[RankedEntry<T>], top: usize, summary: impl Fn(u32, u32) -> String) {
-	let total = ranked_entries.iter().map(|entry| entry.freq_entry.freq).sum();
-	let unique = ranked_entries.len() as u32;
+/// Width (in digits) needed to print the `#idx` column for `len` ranked entries.
+fn digits_for(len: usize) -> usize {
+	let unique = len as u32;
 	let mut digits = if unique > 0 { unique.ilog10() } else { 0 };
 	if 10u32.pow(digits) < unique {
 		digits += 1;
 	}
+	digits as usize
+}
+
+fn print_top<T: Eq + Ord + Display>(ranked_entries: &[RankedEntry<T>], top: usize, summary: impl Fn(u32, u32) -> String) {
+	let total = ranked_entries.iter().map(|entry| entry.freq_entry.freq).sum();
+	let unique = ranked_entries.len() as u32;
+	let digits = digits_for(ranked_entries.len());
 
 	let mut iter = ranked_entries.iter().peekable();
 
 	iter
 		.by_ref()
 		.take(top)
-		.for_each(|entry| println!("{}", entry.to_string(digits as usize)));
+		.for_each(|entry| println!("{}", entry.to_string(digits)));
 	println!("{}", summary(unique, total));
 
 	if iter.peek().is_some() {
@@ -238,13 +1064,225 @@ fn print_top<T: Eq + Ord + Display>(ranked_entries: &[RankedEntry<T>], top: usiz
 			if let Some(response) = prompt() { break response; }
 		};
 		if response {
-			iter.for_each(|entry| println!("{}", entry.to_string(digits as usize)));
+			iter.for_each(|entry| println!("{}", entry.to_string(digits)));
 		}
 	}
 }
 
-fn process_file(path: &str) -> anyhow::Result<()> {
-	let file = File::open(path)?;
+/// Which ranked table the interactive prompt is currently showing.
+#[derive(Clone, Copy)]
+enum View {
+	Albums,
+	Artists,
+}
+
+impl View {
+	fn table<'a>(
+		self,
+		albums: &'a [RankedEntry<SortedName>],
+		artists: &'a [RankedEntry<SortedName>],
+	) -> (&'a [RankedEntry<SortedName>], usize, &'static str) {
+		match self {
+			View::Albums => (albums, TOP_ALBUMS, "albums"),
+			View::Artists => (artists, TOP_ARTISTS, "artists"),
+		}
+	}
+}
+
+/// Shows the top N of the current view, then lets the user type a search string to incrementally
+/// filter the full ranked list by substring, or switch between the album/artist views. An empty
+/// line quits. Matches keep their original global `#idx`/rank from the un-filtered table.
+fn run_interactive(albums: &[RankedEntry<SortedName>], artists: &[RankedEntry<SortedName>]) {
+	let mut view = View::Albums;
+
+	loop {
+		let (ranked, top, label) = view.table(albums, artists);
+		let digits = digits_for(ranked.len());
+		ranked.iter().take(top).for_each(|entry| println!("{}", entry.to_string(digits)));
+		println!("{} {label} listed", ranked.len());
+
+		print!("Search, \"albums\"/\"artists\" to switch view, or Enter to quit: ");
+		io::stdout().flush().expect("Flush STDOUT");
+		let mut input = String::new();
+		if io::stdin().read_line(&mut input).is_err() {
+			break;
+		}
+		let input = input.trim();
+
+		match input.to_ascii_lowercase().as_str() {
+			"" => break,
+			"albums" => view = View::Albums,
+			"artists" => view = View::Artists,
+			query => {
+				let matches = ranked
+					.iter()
+					.filter(|entry| entry.freq_entry.value.to_string().to_ascii_lowercase().contains(query))
+					.collect_vec();
+				if matches.is_empty() {
+					println!("No matches for {query:?}");
+				} else {
+					matches.into_iter().for_each(|entry| println!("{}", entry.to_string(digits)));
+				}
+			}
+		}
+	}
+}
+
+/// Output format for the ranked album/artist tables: `text` keeps the interactive pager, while
+/// `json`/`csv` dump the full ranked list (not just the top N) for other tools to consume.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+	Text,
+	Json,
+	Csv,
+}
+
+impl FromStr for Format {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Format::Text),
+			"json" => Ok(Format::Json),
+			"csv" => Ok(Format::Csv),
+			_ => Err(()),
+		}
+	}
+}
+
+/// A single ranked row, flattened for machine-readable export. `release_year` is only ever
+/// populated for albums, and only when `--musicbrainz` resolved one.
+#[derive(Serialize)]
+struct ExportRow {
+	idx: u32,
+	rank: u32,
+	freq: u32,
+	name: String,
+	release_year: Option<i32>,
+}
+
+impl ExportRow {
+	fn from_ranked<T: Ord + Eq + Display>(ranked: &RankedEntry<T>) -> Self {
+		Self {
+			idx: ranked.idx,
+			rank: ranked.rank,
+			freq: ranked.freq_entry.freq,
+			name: ranked.freq_entry.value.to_string(),
+			release_year: None,
+		}
+	}
+
+	fn with_release_year(mut self, release_year: Option<i32>) -> Self {
+		self.release_year = release_year;
+		self
+	}
+}
+
+fn csv_escape(value: &str) -> String {
+	if value.contains([',', '"', '\n']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_owned()
+	}
+}
+
+fn print_csv_rows(label: &str, rows: &[ExportRow]) {
+	println!("# {label}");
+	println!("rank,freq,name,release_year");
+	for row in rows {
+		let release_year = row.release_year.map(|year| year.to_string()).unwrap_or_default();
+		println!("{},{},{},{release_year}", row.rank, row.freq, csv_escape(&row.name));
+	}
+}
+
+/// Renders the full ranked album/artist lists in the requested non-interactive format.
+fn print_structured(albums: &[ExportRow], artists: &[ExportRow], format: Format) -> anyhow::Result<()> {
+	match format {
+		Format::Text => unreachable!("text format uses the interactive pager instead"),
+		Format::Json => {
+			let payload = serde_json::json!({ "albums": albums, "artists": artists });
+			println!("{}", serde_json::to_string_pretty(&payload)?);
+		}
+		Format::Csv => {
+			print_csv_rows("albums", albums);
+			println!();
+			print_csv_rows("artists", artists);
+		}
+	}
+	Ok(())
+}
+
+/// Canonical album name per raw logged entry, and resolved MusicBrainz release year per canonical
+/// album name (populated only when MusicBrainz resolution is enabled).
+type ResolvedAlbumNames = (HashMap<String, SortedName>, HashMap<SortedName, Option<i32>>);
+
+/// Resolves every distinct raw album entry to a canonical [`SortedName`] once (via MusicBrainz
+/// when enabled, falling back to the [`Canonicalizer`] otherwise), so every function that groups
+/// log entries by album keys them the same way.
+fn resolve_album_names(
+	log: &AlbumLog,
+	canonicalizer: &Canonicalizer,
+	musicbrainz_client: &mut Option<MusicBrainzClient>,
+) -> anyhow::Result<ResolvedAlbumNames> {
+	let mut resolved_names = HashMap::new();
+	let mut release_years = HashMap::new();
+
+	for (_, entry) in log.dated_album_entries() {
+		if resolved_names.contains_key(&entry.value) {
+			continue;
+		}
+
+		let resolution = match musicbrainz_client {
+			Some(client) => client.resolve(&entry.value)?,
+			None => None,
+		};
+		let sorted_name = match &resolution {
+			Some(resolution) => resolution.canonical_name(),
+			None => canonicalizer.canonicalize_album_entry(&entry.value),
+		};
+		if let Some(resolution) = &resolution {
+			release_years.insert(sorted_name.clone(), resolution.release_year);
+		}
+		resolved_names.insert(entry.value.clone(), sorted_name);
+	}
+
+	Ok((resolved_names, release_years))
+}
+
+/// Annotates the top albums with their MusicBrainz release year, when one was resolved.
+fn print_album_years(ranked_albums: &[RankedEntry<SortedName>], release_years: &HashMap<SortedName, Option<i32>>) {
+	println!("\n== Release year (MusicBrainz) ==");
+	for ranked in ranked_albums.iter().take(TOP_ALBUMS) {
+		match release_years.get(&ranked.freq_entry.value) {
+			Some(Some(year)) => println!("{}: {year}", ranked.freq_entry.value),
+			_ => println!("{}: unknown", ranked.freq_entry.value),
+		}
+	}
+}
+
+fn process_file(args: Args) -> anyhow::Result<()> {
+	let Args { file: path, by, alias_file, query, query_file, format, interactive, musicbrainz, musicbrainz_cache } = args;
+
+	let canonicalizer = match alias_file.as_deref() {
+		Some(path) => Canonicalizer::load(path)?,
+		None => Canonicalizer::new(),
+	};
+
+	let mut musicbrainz_client = match musicbrainz {
+		true => Some(MusicBrainzClient::load(musicbrainz_cache.as_deref().unwrap_or(DEFAULT_MUSICBRAINZ_CACHE))?),
+		false => None,
+	};
+
+	let query_text = match query_file.as_deref() {
+		Some(path) => Some(std::fs::read_to_string(path)?),
+		None => query,
+	};
+	let predicate = query_text
+		.map(|text| text.trim().parse::<Predicate>())
+		.transpose()
+		.map_err(|err| anyhow::anyhow!(err))?;
+
+	let file = File::open(&path)?;
 	let reader = BufReader::new(file);
 
 	let log = reader
@@ -259,16 +1297,17 @@ fn process_file(path: &str) -> anyhow::Result<()> {
 			acc
 		});
 
+	let (resolved_names, release_years) = resolve_album_names(&log, &canonicalizer, &mut musicbrainz_client)?;
+
 	let album_freq =
-		log.flattened_album_entries()
-			.fold(HashMap::<String, u32>::new(), |mut acc, entry| {
-				acc.entry(entry.value.clone())
+		filter_dated_entries(&log, predicate.as_ref())
+			.fold(HashMap::<SortedName, u32>::new(), |mut acc, (_, entry)| {
+				acc.entry(resolved_names[&entry.value].clone())
 					.or_default()
 					.add_assign(entry.freq);
 				acc
 			});
 
-
 	let ranked_entries =
 		RankedEntry::from_freq_entries(
 			album_freq
@@ -276,21 +1315,14 @@ fn process_file(path: &str) -> anyhow::Result<()> {
 				.map(|(album, freq)| FreqEntry::new(freq, album))
 		);
 
-	print_top(
-		&ranked_entries,
-		TOP_ALBUMS,
-		|unique, total| format!("{unique} albums listed, {total} albums listened"),
-	);
-
-
 	let artist_counter =
 		ranked_entries
 			.iter()
 			.flat_map(|ranked_entry| {
-				get_artists(&ranked_entry.freq_entry.value)
+				get_artists(&ranked_entry.freq_entry.value.display)
 					.into_iter()
 					.flatten()
-					.map(|artist| (artist, ranked_entry.freq_entry.freq))
+					.map(|artist| (canonicalizer.canonicalize(&artist), ranked_entry.freq_entry.freq))
 			})
 			.fold(Counter::new(), |mut acc, (artist, freq)| {
 				acc.add(artist, freq);
@@ -298,7 +1330,41 @@ fn process_file(path: &str) -> anyhow::Result<()> {
 			});
 
 	let ranked_artists = RankedEntry::from_freq_entries(artist_counter.to_freq_entries());
-	print_top(&ranked_artists, TOP_ARTISTS, |unique, total| format!("{unique} artists listed, {total} artists listened"));
+
+	if format == Format::Text {
+		if interactive {
+			run_interactive(&ranked_entries, &ranked_artists);
+		} else {
+			print_top(
+				&ranked_entries,
+				TOP_ALBUMS,
+				|unique, total| format!("{unique} albums listed, {total} albums listened"),
+			);
+			print_top(&ranked_artists, TOP_ARTISTS, |unique, total| format!("{unique} artists listed, {total} artists listened"));
+		}
+
+		if musicbrainz {
+			print_album_years(&ranked_entries, &release_years);
+		}
+
+		print_monthly_trend(&log, predicate.as_ref());
+		print_album_peaks(&log, &ranked_entries, &resolved_names, predicate.as_ref());
+		print_artist_streaks(&log, &ranked_artists, &resolved_names, &canonicalizer, predicate.as_ref());
+
+		if let Some(granularity) = by {
+			print_periodic_tables(&log, granularity, &resolved_names, &canonicalizer, predicate.as_ref());
+		}
+	} else {
+		let albums = ranked_entries
+			.iter()
+			.map(|ranked| {
+				ExportRow::from_ranked(ranked)
+					.with_release_year(release_years.get(&ranked.freq_entry.value).copied().flatten())
+			})
+			.collect_vec();
+		let artists = ranked_artists.iter().map(ExportRow::from_ranked).collect_vec();
+		print_structured(&albums, &artists, format)?;
+	}
 
 	Ok(())
 }